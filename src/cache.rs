@@ -0,0 +1,21 @@
+use std::fs;
+
+use crate::WeatherData;
+
+const CACHE_PATH: &str = "/tmp/pi-display-weather.json";
+
+/// Loads the last successfully fetched [`WeatherData`] from disk, if any.
+/// Used on startup, before the first fetch completes, so the panel can show
+/// stale data instead of "Loading..." forever.
+pub(crate) fn load() -> Option<WeatherData> {
+    let contents = fs::read_to_string(CACHE_PATH).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Persists `weather` to disk so it survives a restart or a later fetch
+/// failure.
+pub(crate) fn save(weather: &WeatherData) {
+    if let Ok(json) = serde_json::to_string(weather) {
+        let _ = fs::write(CACHE_PATH, json);
+    }
+}
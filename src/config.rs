@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+/// Temperature unit used both for display and for the Open-Meteo API request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TemperatureUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+impl TemperatureUnit {
+    /// Suffix appended to a rendered temperature, e.g. `"21c"`.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "c",
+            TemperatureUnit::Fahrenheit => "f",
+        }
+    }
+
+    /// Value of Open-Meteo's `temperature_unit` query parameter.
+    pub fn api_param(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "celsius",
+            TemperatureUnit::Fahrenheit => "fahrenheit",
+        }
+    }
+}
+
+impl Default for TemperatureUnit {
+    fn default() -> Self {
+        TemperatureUnit::Celsius
+    }
+}
+
+/// User-configurable location, timezone, and refresh settings.
+///
+/// Loaded from `~/.config/pi-display/config.toml`, with [`Config::default`]
+/// supplying any field the file omits (or the whole config, if the file is
+/// missing or unreadable). This lets the same binary be deployed anywhere
+/// without recompiling.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timezone: String,
+    pub update_interval_secs: u64,
+    pub temperature_unit: TemperatureUnit,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        // Brisbane, Australia
+        Self {
+            latitude: -27.4698,
+            longitude: 153.0251,
+            timezone: "Australia/Brisbane".to_string(),
+            update_interval_secs: 30 * 60,
+            temperature_unit: TemperatureUnit::Celsius,
+        }
+    }
+}
+
+impl Config {
+    /// Load configuration from `~/.config/pi-display/config.toml`, falling
+    /// back to [`Config::default`] when the file is missing, unreadable, or
+    /// fails to parse.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        let Ok(contents) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+
+        toml::from_str(&contents).unwrap_or_default()
+    }
+
+    pub fn update_interval(&self) -> Duration {
+        Duration::from_secs(self.update_interval_secs)
+    }
+
+    fn path() -> Option<PathBuf> {
+        let mut path = dirs::config_dir()?;
+        path.push("pi-display");
+        path.push("config.toml");
+        Some(path)
+    }
+}
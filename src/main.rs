@@ -1,6 +1,10 @@
-use std::time::{Duration, Instant};
+mod cache;
+mod config;
 
-use chrono::{Local, Timelike};
+use std::sync::mpsc;
+use std::thread;
+
+use chrono::{DateTime, Local, Timelike};
 use crossterm::event::{self, Event, KeyCode};
 use ratatui::{
     Frame,
@@ -8,17 +12,15 @@ use ratatui::{
     style::{Color, Style},
     widgets::{Block, Borders, Clear, Paragraph},
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tui_big_text::{BigText, PixelSize};
 
-// Brisbane, Australia coordinates
-const LATITUDE: f64 = -27.4698;
-const LONGITUDE: f64 = 153.0251;
-const WEATHER_UPDATE_INTERVAL: Duration = Duration::from_secs(30 * 60); // 30 minutes
+use config::Config;
 
 #[derive(Debug, Deserialize)]
 struct OpenMeteoResponse {
     current: CurrentWeather,
+    hourly: HourlyWeather,
     daily: DailyWeather,
 }
 
@@ -26,6 +28,13 @@ struct OpenMeteoResponse {
 struct CurrentWeather {
     temperature_2m: f64,
     weather_code: u8,
+    is_day: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlyWeather {
+    time: Vec<String>,
+    temperature_2m: Vec<f64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,45 +43,136 @@ struct DailyWeather {
     weather_code: Vec<u8>,
     temperature_2m_max: Vec<f64>,
     temperature_2m_min: Vec<f64>,
+    sunrise: Vec<String>,
+    sunset: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
-struct WeatherData {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct WeatherData {
     current_temp: f64,
     current_condition: String,
+    current_icon: String,
+    hourly_temps: Vec<f64>,
     forecast: Vec<ForecastDay>,
+    air_quality: Option<AirQuality>,
+    sunrise: Option<chrono::NaiveDateTime>,
+    sunset: Option<chrono::NaiveDateTime>,
+    fetched_at: DateTime<Local>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ForecastDay {
     day_name: String,
     high: f64,
     low: f64,
     condition: String,
+    icon: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AirQualityResponse {
+    current: CurrentAirQuality,
+}
+
+#[derive(Debug, Deserialize)]
+struct CurrentAirQuality {
+    european_aqi: f64,
+    pm2_5: f64,
+    pm10: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AirQuality {
+    european_aqi: f64,
+    pm2_5: f64,
+    pm10: f64,
+}
+
+impl AirQuality {
+    /// Standard European AQI bands, used to color the readout.
+    fn style(&self) -> Style {
+        let color = match self.european_aqi as i64 {
+            ..=20 => Color::Green,
+            21..=40 => Color::Yellow,
+            41..=60 => Color::Rgb(255, 165, 0), // Orange
+            _ => Color::Red,
+        };
+        Style::default().fg(color)
+    }
 }
 
-fn weather_code_to_condition(code: u8) -> &'static str {
+/// Maps a WMO weather code and day/night flag to a short condition label and
+/// an ASCII/Unicode glyph, so clear skies render a sun by day and a moon by
+/// night, cloud cover shades differently, and so on.
+fn weather_code_to_condition(code: u8, is_day: bool) -> (&'static str, &'static str) {
     match code {
-        0 => "Clear",
-        1..=3 => "Cloudy",
-        45..=48 => "Fog",
-        51..=67 => "Rain",
-        71..=77 => "Snow",
-        80..=82 => "Showers",
-        95..=99 => "Storm",
-        _ => "Unknown",
+        0 => ("Clear", if is_day { "☀" } else { "🌙" }),
+        1..=3 => ("Cloudy", if is_day { "⛅" } else { "☁" }),
+        45..=48 => ("Fog", "🌫"),
+        51..=67 => ("Rain", "🌧"),
+        71..=77 => ("Snow", "❄"),
+        80..=82 => ("Showers", if is_day { "🌦" } else { "🌧" }),
+        95..=99 => ("Storm", "⛈"),
+        _ => ("Unknown", "?"),
     }
 }
 
-fn fetch_weather() -> Option<WeatherData> {
+const SPARK_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Formats how long ago `fetched_at` was, for the "updated Xm ago" marker
+/// shown next to stale (e.g. cached or failed-to-refresh) weather data.
+fn weather_age(fetched_at: DateTime<Local>) -> String {
+    let minutes = (Local::now() - fetched_at).num_minutes();
+    format!("updated {minutes}m ago")
+}
+
+/// Resamples `values` to exactly `width` columns (linearly interpolating
+/// between source samples) and renders each column as one of the eight
+/// Unicode block glyphs, scaled between the min and max of `values`.
+fn sparkline(values: &[f64], width: usize) -> String {
+    if values.is_empty() || width == 0 {
+        return String::new();
+    }
+
+    let n = values.len();
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+
+    (0..width)
+        .map(|x| {
+            let value = if n == 1 || width == 1 {
+                values[0]
+            } else {
+                let pos = x as f64 * (n - 1) as f64 / (width - 1) as f64;
+                let lo = pos.floor() as usize;
+                let hi = pos.ceil() as usize;
+                values[lo] + (values[hi] - values[lo]) * (pos - lo as f64)
+            };
+            let level = if range == 0.0 {
+                7
+            } else {
+                (((value - min) / range) * 7.0).floor().clamp(0.0, 7.0) as usize
+            };
+            SPARK_BLOCKS[level]
+        })
+        .collect()
+}
+
+fn fetch_weather(config: &Config) -> Option<WeatherData> {
     let url = format!(
         "https://api.open-meteo.com/v1/forecast?\
         latitude={}&longitude={}&\
-        current=temperature_2m,weather_code&\
-        daily=weather_code,temperature_2m_max,temperature_2m_min&\
-        timezone=Australia/Brisbane&\
+        current=temperature_2m,weather_code,is_day&\
+        hourly=temperature_2m&\
+        daily=weather_code,temperature_2m_max,temperature_2m_min,sunrise,sunset&\
+        timezone={}&\
+        temperature_unit={}&\
         forecast_days=8",
-        LATITUDE, LONGITUDE
+        config.latitude,
+        config.longitude,
+        config.timezone,
+        config.temperature_unit.api_param()
     );
 
     let response = reqwest::blocking::get(&url).ok()?;
@@ -87,25 +187,103 @@ fn fetch_weather() -> Option<WeatherData> {
         .enumerate()
         .map(|(i, date)| {
             let idx = i + 1; // Offset for skipped today
+            // Forecast days have no is_day flag; always render the daytime glyph.
+            let (condition, icon) = weather_code_to_condition(data.daily.weather_code[idx], true);
             ForecastDay {
                 day_name: chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
                     .map(|d| d.format("%a").to_string())
                     .unwrap_or_else(|_| "???".to_string()),
                 high: data.daily.temperature_2m_max[idx],
                 low: data.daily.temperature_2m_min[idx],
-                condition: weather_code_to_condition(data.daily.weather_code[idx]).to_string(),
+                condition: condition.to_string(),
+                icon: icon.to_string(),
             }
         })
         .collect();
 
+    let (current_condition, current_icon) =
+        weather_code_to_condition(data.current.weather_code, data.current.is_day != 0);
+
+    // Next ~24 hours from now, for the sparkline.
+    let now = chrono::Local::now().naive_local();
+    let hourly_temps: Vec<f64> = data
+        .hourly
+        .time
+        .iter()
+        .zip(data.hourly.temperature_2m.iter())
+        .filter(|(time, _)| {
+            chrono::NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M")
+                .map(|dt| dt >= now)
+                .unwrap_or(false)
+        })
+        .take(24)
+        .map(|(_, temp)| *temp)
+        .collect();
+
+    // Today's sunrise/sunset, for the daylight-remaining countdown.
+    let sunrise = chrono::NaiveDateTime::parse_from_str(&data.daily.sunrise[0], "%Y-%m-%dT%H:%M").ok();
+    let sunset = chrono::NaiveDateTime::parse_from_str(&data.daily.sunset[0], "%Y-%m-%dT%H:%M").ok();
+
     Some(WeatherData {
         current_temp: data.current.temperature_2m,
-        current_condition: weather_code_to_condition(data.current.weather_code).to_string(),
+        current_condition: current_condition.to_string(),
+        current_icon: current_icon.to_string(),
+        hourly_temps,
         forecast,
+        air_quality: fetch_air_quality(config),
+        sunrise,
+        sunset,
+        fetched_at: Local::now(),
+    })
+}
+
+fn fetch_air_quality(config: &Config) -> Option<AirQuality> {
+    let url = format!(
+        "https://air-quality-api.open-meteo.com/v1/air-quality?\
+        latitude={}&longitude={}&\
+        current=european_aqi,pm2_5,pm10&\
+        timezone={}",
+        config.latitude, config.longitude, config.timezone
+    );
+
+    let response = reqwest::blocking::get(&url).ok()?;
+    let data: AirQualityResponse = response.json().ok()?;
+
+    Some(AirQuality {
+        european_aqi: data.current.european_aqi,
+        pm2_5: data.current.pm2_5,
+        pm10: data.current.pm10,
     })
 }
 
+/// Spawns a background worker that owns the blocking HTTP client, fetching
+/// weather immediately and then on every `config.update_interval()`, pushing
+/// each successful fetch over the returned channel. Keeps the render loop
+/// free to redraw the clock at its own cadence instead of blocking on the
+/// network.
+fn spawn_weather_worker(config: Config) -> mpsc::Receiver<WeatherData> {
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        loop {
+            if let Some(weather) = fetch_weather(&config) {
+                cache::save(&weather);
+                if tx.send(weather).is_err() {
+                    // Receiver dropped; main loop has exited.
+                    break;
+                }
+            }
+            thread::sleep(config.update_interval());
+        }
+    });
+
+    rx
+}
+
 fn main() -> std::io::Result<()> {
+    let config = Config::load();
+    let weather_rx = spawn_weather_worker(config.clone());
+
     ratatui::run(|terminal| {
         // Clear the entire screen on startup
         terminal.clear()?;
@@ -117,38 +295,36 @@ fn main() -> std::io::Result<()> {
             })?;
         }
 
-        // Fetch weather on startup
-        let mut weather: Option<WeatherData> = fetch_weather();
-        let mut last_weather_fetch = Instant::now();
+        // Show the last successfully fetched weather immediately, even if
+        // it's from a previous run, rather than "Loading..." until the
+        // first network round-trip completes.
+        let mut weather: Option<WeatherData> = cache::load();
 
         loop {
+            // Drain any weather pushed by the background worker without blocking.
+            while let Ok(new_weather) = weather_rx.try_recv() {
+                weather = Some(new_weather);
+            }
+
             let now = Local::now();
             // Blink every half second based on milliseconds
             let show_colon = (now.timestamp_millis() / 500) % 2 == 0;
 
-            terminal.draw(|frame| render(frame, show_colon, weather.as_ref()))?;
+            terminal.draw(|frame| render(frame, show_colon, weather.as_ref(), &config))?;
 
             // Poll for events with 500ms timeout to update the blink
-            if event::poll(Duration::from_millis(500))? {
+            if event::poll(std::time::Duration::from_millis(500))? {
                 if let Event::Key(key) = event::read()? {
                     if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
                         break Ok(());
                     }
                 }
             }
-
-            // Refresh weather every 30 minutes
-            if last_weather_fetch.elapsed() >= WEATHER_UPDATE_INTERVAL {
-                if let Some(new_weather) = fetch_weather() {
-                    weather = Some(new_weather);
-                }
-                last_weather_fetch = Instant::now();
-            }
         }
     })
 }
 
-fn render(frame: &mut Frame, show_colon: bool, weather: Option<&WeatherData>) {
+fn render(frame: &mut Frame, show_colon: bool, weather: Option<&WeatherData>, config: &Config) {
     // Clear the entire frame area
     frame.render_widget(Clear, frame.area());
 
@@ -157,13 +333,18 @@ fn render(frame: &mut Frame, show_colon: bool, weather: Option<&WeatherData>) {
         Layout::horizontal([Constraint::Ratio(2, 3), Constraint::Ratio(1, 3)]).areas(frame.area());
 
     // Render time/date section
-    render_time_date(frame, time_section, show_colon);
+    render_time_date(frame, time_section, show_colon, weather);
 
     // Render weather section
-    render_weather(frame, weather_section, weather);
+    render_weather(frame, weather_section, weather, config);
 }
 
-fn render_time_date(frame: &mut Frame, area: ratatui::layout::Rect, show_colon: bool) {
+fn render_time_date(
+    frame: &mut Frame,
+    area: ratatui::layout::Rect,
+    show_colon: bool,
+    weather: Option<&WeatherData>,
+) {
     // Add border around time/date panel
     let block = Block::default()
         .borders(Borders::ALL)
@@ -180,11 +361,13 @@ fn render_time_date(frame: &mut Frame, area: ratatui::layout::Rect, show_colon:
     // Format date as "Saturday, January 4"
     let date_str = now.format("%A, %B %-d").to_string();
 
-    // Create vertical layout: time on top, date below
-    let [time_area, _, date_area] = Layout::vertical([
+    // Create vertical layout: time on top, date below, sunrise/sunset strip at the bottom
+    let [time_area, _, date_area, _, sun_area] = Layout::vertical([
         Constraint::Length(8), // BigText height
         Constraint::Length(1), // Spacer
         Constraint::Length(2), // Date text height
+        Constraint::Length(1), // Spacer
+        Constraint::Length(1), // Sunrise/sunset strip
     ])
     .flex(Flex::Center)
     .areas(inner);
@@ -205,9 +388,58 @@ fn render_time_date(frame: &mut Frame, area: ratatui::layout::Rect, show_colon:
         .centered();
 
     frame.render_widget(date_widget, date_area);
+
+    // Sunrise/sunset times plus a daylight-remaining countdown
+    if let Some(weather) = weather {
+        let sun_text = daylight_strip(weather, now.naive_local());
+        let sun_widget = Paragraph::new(sun_text)
+            .style(Style::default().fg(Color::DarkGray))
+            .centered();
+        frame.render_widget(sun_widget, sun_area);
+    }
 }
 
-fn render_weather(frame: &mut Frame, area: ratatui::layout::Rect, weather: Option<&WeatherData>) {
+/// Formats today's sunrise/sunset times plus a countdown to whichever comes
+/// next (time-until-sunset during the day, time-until-sunrise at night).
+///
+/// `weather` may be a disk-cached reading from a previous day (loaded on
+/// startup before the first fetch completes); if the cached sunrise isn't
+/// for `now`'s date, the countdown would be computed against a stale target
+/// and could come out negative, so we fall back to showing just the times.
+fn daylight_strip(weather: &WeatherData, now: chrono::NaiveDateTime) -> String {
+    let (Some(sunrise), Some(sunset)) = (weather.sunrise, weather.sunset) else {
+        return String::new();
+    };
+
+    if sunrise.date() != now.date() {
+        return format!("☀ {} ☾ {}", sunrise.format("%H:%M"), sunset.format("%H:%M"));
+    }
+
+    let (label, target) = if now < sunrise {
+        ("sunrise", sunrise)
+    } else if now < sunset {
+        ("sunset", sunset)
+    } else {
+        ("sunrise", sunrise + chrono::Duration::days(1))
+    };
+
+    let remaining = target - now;
+    format!(
+        "☀ {} ☾ {} · {} in {}h{:02}m",
+        sunrise.format("%H:%M"),
+        sunset.format("%H:%M"),
+        label,
+        remaining.num_hours(),
+        remaining.num_minutes() % 60
+    )
+}
+
+fn render_weather(
+    frame: &mut Frame,
+    area: ratatui::layout::Rect,
+    weather: Option<&WeatherData>,
+    config: &Config,
+) {
     // Add border around weather panel (no title)
     let block = Block::default()
         .borders(Borders::ALL)
@@ -224,18 +456,27 @@ fn render_weather(frame: &mut Frame, area: ratatui::layout::Rect, weather: Optio
         return;
     };
 
-    // Split weather area: current, condition, forecast (with spacing)
-    let [current_area, condition_area, _, forecast_area] = Layout::vertical([
-        Constraint::Length(4), // Current temp
-        Constraint::Length(1), // Current condition
-        Constraint::Length(1), // Spacer
-        Constraint::Length(5), // 5 day forecast
-    ])
-    .flex(Flex::Center)
-    .areas(inner);
+    // Split weather area: current, condition, sparkline, forecast, air quality (with spacing)
+    let [current_area, condition_area, _, sparkline_area, _, forecast_area, _, air_quality_area] =
+        Layout::vertical([
+            Constraint::Length(4), // Current temp
+            Constraint::Length(1), // Current condition
+            Constraint::Length(1), // Spacer
+            Constraint::Length(1), // 24h temperature sparkline
+            Constraint::Length(1), // Spacer
+            Constraint::Length(5), // 5 day forecast
+            Constraint::Length(1), // Spacer
+            Constraint::Length(1), // Air quality
+        ])
+        .flex(Flex::Center)
+        .areas(inner);
 
     // Current weather as big text
-    let current_text = format!("{}c", weather.current_temp.round() as i32);
+    let current_text = format!(
+        "{}{}",
+        weather.current_temp.round() as i32,
+        config.temperature_unit.suffix()
+    );
     let current_widget = BigText::builder()
         .pixel_size(PixelSize::HalfHeight)
         .style(Style::default().fg(Color::Gray))
@@ -245,23 +486,47 @@ fn render_weather(frame: &mut Frame, area: ratatui::layout::Rect, weather: Optio
 
     frame.render_widget(current_widget, current_area);
 
-    let condition_text = format!("{}", weather.current_condition);
+    // Only call out the data's age once it is older than one update interval
+    // (a refresh failed or has not landed yet); fresh data needs no
+    // "updated Xm ago" caveat.
+    let is_stale = (Local::now() - weather.fetched_at)
+        .to_std()
+        .is_ok_and(|age| age > config.update_interval());
+    let condition_text = if is_stale {
+        format!(
+            "{} {} · {}",
+            weather.current_icon,
+            weather.current_condition,
+            weather_age(weather.fetched_at)
+        )
+    } else {
+        format!("{} {}", weather.current_icon, weather.current_condition)
+    };
     let condition_widget = Paragraph::new(condition_text)
         .style(Style::default().fg(Color::Gray))
         .centered();
 
     frame.render_widget(condition_widget, condition_area);
 
+    // 24h temperature sparkline, resampled to the panel's inner width
+    let spark_text = sparkline(&weather.hourly_temps, sparkline_area.width as usize);
+    let spark_widget = Paragraph::new(spark_text)
+        .style(Style::default().fg(Color::Gray))
+        .centered();
+    frame.render_widget(spark_widget, sparkline_area);
+
     // Forecast
     let forecast_lines: Vec<String> = weather
         .forecast
         .iter()
         .map(|day| {
+            let unit = config.temperature_unit.suffix();
             format!(
-                "{} {}c/{}c {}",
+                "{} {}{unit}/{}{unit} {} {}",
                 day.day_name,
                 day.low.round() as i32,
                 day.high.round() as i32,
+                day.icon,
                 day.condition
             )
         })
@@ -272,4 +537,18 @@ fn render_weather(frame: &mut Frame, area: ratatui::layout::Rect, weather: Optio
         .style(Style::default().fg(Color::Gray))
         .centered();
     frame.render_widget(forecast_widget, forecast_area);
+
+    // Air quality, color-coded by AQI band
+    if let Some(air_quality) = &weather.air_quality {
+        let air_quality_text = format!(
+            "AQI {} (PM2.5 {:.0} PM10 {:.0})",
+            air_quality.european_aqi.round() as i32,
+            air_quality.pm2_5,
+            air_quality.pm10
+        );
+        let air_quality_widget = Paragraph::new(air_quality_text)
+            .style(air_quality.style())
+            .centered();
+        frame.render_widget(air_quality_widget, air_quality_area);
+    }
 }